@@ -1,7 +1,97 @@
 use rand::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
 const NO_DATA: &'static str = "no data: can not choose from empty set";
 
+/// An error returned by the fallible, generically-weighted sampling functions
+///
+/// See [choose_multiple_weighted_checked].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightError {
+    /// A weight was negative, non-finite, or otherwise invalid for its type.
+    InvalidWeight,
+    /// The cumulative sum of the weights overflowed the weight type.
+    Overflow,
+}
+
+impl std::fmt::Display for WeightError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeightError::InvalidWeight => {
+                write!(f, "invalid weight: weights must be finite and non-negative")
+            }
+            WeightError::Overflow => write!(f, "the cumulative sum of the weights overflowed"),
+        }
+    }
+}
+
+impl std::error::Error for WeightError {}
+
+/// A weight usable with the fallible weighted-sampling functions
+///
+/// Implemented for the unsigned integer types, whose cumulative sum is exact
+/// and whose overflow is reported via `checked_add`, and for `f64`, matching
+/// the existing floating-point semantics (a non-finite or negative weight is
+/// an error instead of a debug-assert panic).
+pub trait Weight: Copy + Default + PartialOrd {
+    /// Add two weights, returning `None` on overflow.
+    fn checked_add(self, other: Self) -> Option<Self>;
+    /// Check that this weight is legal on its own (finite and non-negative).
+    fn validate(self) -> Result<(), WeightError>;
+    /// Convert the total weight to `f64`, for computing the SUS arm spacing.
+    fn to_f64(self) -> f64;
+    /// The threshold below which a total weight over `count` elements counts
+    /// as "effectively zero" and falls back to uniform selection.
+    ///
+    /// Exact for the integer impls, since their cumulative sum is exact.
+    /// `f64` overrides this to fuzz by `f64::EPSILON * count`, matching
+    /// [choose_multiple_weighted]'s tolerance for floating-point summation
+    /// drift.
+    fn zero_threshold(_count: usize) -> Self {
+        Self::default()
+    }
+}
+
+macro_rules! impl_weight_for_uint {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl Weight for $t {
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+                fn validate(self) -> Result<(), WeightError> {
+                    Ok(())
+                }
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+impl_weight_for_uint!(u8, u16, u32, u64, u128, usize);
+
+impl Weight for f64 {
+    fn checked_add(self, other: Self) -> Option<Self> {
+        let sum = self + other;
+        sum.is_finite().then_some(sum)
+    }
+    fn validate(self) -> Result<(), WeightError> {
+        if self.is_finite() && self >= 0.0 {
+            Ok(())
+        } else {
+            Err(WeightError::InvalidWeight)
+        }
+    }
+    fn to_f64(self) -> f64 {
+        self
+    }
+    fn zero_threshold(count: usize) -> Self {
+        f64::EPSILON * count as f64
+    }
+}
+
 /// The stochastic universal sampling algorithm
 ///
 /// Chooses `amount` elements at random, with repetition, and in random order.
@@ -80,9 +170,422 @@ where
     return results;
 }
 
+/// The stochastic universal sampling algorithm, without repetition
+///
+/// Chooses `amount` distinct elements at random, without repetition, and in
+/// random order. The likelihood of each element's inclusion (and of being
+/// drawn early) is specified by the `weights` array, so higher-weighted
+/// indices tend to appear before lower-weighted ones. All weights must be
+/// greater than or equal to zero.
+///
+/// This is backed by a Fenwick (binary indexed) tree so that each of the
+/// `amount` draws costs `O(log n)` instead of re-scanning the weights array.
+///
+/// If `amount` is greater than the number of elements with weight greater
+/// than zero, then the remaining slots are filled with zero-weight elements
+/// in random order. If `amount` is greater than `weights.len()`, then the
+/// result simply contains every index once.
+///
+/// Returns a vector of indices into the weights array.
+pub fn choose_multiple_weighted_without_replacement<R>(
+    rng: &mut R,
+    amount: usize,
+    weights: &[f64],
+) -> Vec<usize>
+where
+    R: Rng + ?Sized,
+{
+    if amount == 0 {
+        return vec![];
+    } else {
+        assert!(!weights.is_empty(), "{NO_DATA}");
+    }
+    let n = weights.len();
+    for &weight in weights {
+        debug_assert!(weight >= 0.0);
+    }
+    let mut sum: f64 = weights.iter().sum();
+    assert!(sum.is_finite());
+    let mut zeros: Vec<usize> = (0..n).filter(|&idx| weights[idx] == 0.0).collect();
+    // Build the Fenwick tree: `tree[i]` holds the sum of a contiguous block
+    // of the original weights, determined by the lowest set bit of `i`.
+    let mut tree = vec![0.0; n + 1];
+    for i in 1..=n {
+        tree[i] += weights[i - 1];
+        let parent = i + (i & i.wrapping_neg());
+        if parent <= n {
+            tree[parent] += tree[i];
+        }
+    }
+    let top_bit = 1usize << (usize::BITS - 1 - n.leading_zeros());
+    let mut samples = Vec::with_capacity(amount.min(n));
+    // The number of indices with a (strictly) positive weight, i.e. the
+    // indices the tree draw below is able to select. `sum` drifting to
+    // (near) zero isn't a safe signal to stop on its own: a very small but
+    // positive remaining weight can make `sum` look negligible without the
+    // index carrying it having actually been drawn, so the loop must keep
+    // going until every positive-weight index has been selected, not just
+    // until `sum` looks exhausted.
+    let positive_count = n - zeros.len();
+    let mut drawn = vec![false; n];
+    while samples.len() < amount && samples.len() < positive_count {
+        let r = rng.gen::<f64>() * sum;
+        // Descend the tree from the highest power of two <= n, testing at
+        // each step whether the accumulated prefix plus the candidate node's
+        // weight is still <= `r`.
+        let mut idx = 0;
+        let mut remaining = r;
+        let mut bit = top_bit;
+        while bit > 0 {
+            let next = idx + bit;
+            if next <= n && tree[next] <= remaining {
+                idx = next;
+                remaining -= tree[next];
+            }
+            bit >>= 1;
+        }
+        let mut selected = idx;
+        // A tiny weight can be absorbed by rounding into a tree node, which
+        // leaves the descent past the last index or on an index already
+        // drawn; take the first undrawn positive-weight index instead.
+        if selected >= n || drawn[selected] || weights[selected] == 0.0 {
+            selected = (0..n)
+                .find(|&k| !drawn[k] && weights[k] > 0.0)
+                .expect("a positive-weight index remains");
+        }
+        drawn[selected] = true;
+        samples.push(selected);
+        // Remove the selected weight from every tree node whose range covers it.
+        let weight = weights[selected];
+        let mut i = selected + 1;
+        while i <= n {
+            tree[i] -= weight;
+            i += i & i.wrapping_neg();
+        }
+        sum -= weight;
+        // Guard against floating-point drift making `sum` slightly negative.
+        if sum < 0.0 {
+            sum = 0.0;
+        }
+    }
+    // All of the positive-weight indices are exhausted; fill the remainder
+    // with zero-weight indices in random order.
+    if samples.len() < amount && !zeros.is_empty() {
+        zeros.shuffle(rng);
+        let remaining = amount - samples.len();
+        samples.extend(zeros.into_iter().take(remaining));
+    }
+    samples
+}
+
+/// The stochastic universal sampling algorithm, generic over the weight type
+///
+/// Chooses `amount` elements at random, with repetition, and in random
+/// order, the same as [choose_multiple_weighted], except that `weights` may
+/// hold any [Weight] (for example `u32` or `u64`) instead of only `f64`.
+/// Computing the cumulative sum in the integer domain avoids the precision
+/// loss that `f64` suffers from for large integer weights.
+///
+/// Unlike [choose_multiple_weighted], invalid weights and an overflowing
+/// cumulative sum are reported as an `Err` instead of panicking.
+///
+/// Returns a vector of indices into the weights array.
+pub fn choose_multiple_weighted_checked<R, W>(
+    rng: &mut R,
+    amount: usize,
+    weights: &[W],
+) -> Result<Vec<usize>, WeightError>
+where
+    R: Rng + ?Sized,
+    W: Weight,
+{
+    if amount == 0 {
+        return Ok(vec![]);
+    } else {
+        assert!(!weights.is_empty(), "{NO_DATA}");
+    }
+    // Apply a cumulative summation to the weights, staying in the integer
+    // domain and checking every step for overflow.
+    let mut cumulative = Vec::with_capacity(weights.len());
+    let mut sum = W::default();
+    for &weight in weights {
+        weight.validate()?;
+        sum = sum.checked_add(weight).ok_or(WeightError::Overflow)?;
+        cumulative.push(sum);
+    }
+    // Check for all zero weights.
+    let total_weight = *cumulative.last().expect("Internal Error");
+    if total_weight <= W::zero_threshold(weights.len()) {
+        return Ok(choose_multiple(rng, amount, weights.len()));
+    }
+    let total_weight = total_weight.to_f64();
+    assert!(total_weight.is_finite());
+    // Generate the random numbers to sample from the weights cumsum.
+    let arm_spacing = total_weight / (amount as f64);
+    let arm_offset = rng.gen::<f64>() * arm_spacing;
+    // Find the indices of random numbers in the weights cumsum.
+    let mut samples = Vec::with_capacity(amount);
+    let mut idx = 0;
+    for arm in 0..amount {
+        let arm = (arm as f64) * arm_spacing + arm_offset;
+        while idx < cumulative.len() && cumulative[idx].to_f64() < arm {
+            idx += 1;
+        }
+        samples.push(idx);
+    }
+    // Shuffle the random sample to break up any runs of repeated elements.
+    samples.shuffle(rng);
+    Ok(samples)
+}
+
+// A candidate kept in the A-Res reservoir, ordered by its random key.
+struct StreamCandidate {
+    key: f64,
+    index: usize,
+}
+
+impl PartialEq for StreamCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+impl Eq for StreamCandidate {}
+impl PartialOrd for StreamCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for StreamCandidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key
+            .partial_cmp(&other.key)
+            .expect("weight keys are never NaN")
+    }
+}
+
+/// Weighted sampling without replacement over a one-pass stream of weights
+///
+/// Draws `amount` distinct indices, with likelihood proportional to weight,
+/// from `weights`, whose length and total weight are not known in advance
+/// and may not fit in memory. This complements [choose_multiple_weighted],
+/// which requires a slice and samples with repetition.
+///
+/// Uses the Efraimidis-Spirakis A-Res reservoir algorithm: every item with
+/// weight greater than zero is assigned a key `u.powf(1.0 / weight)` for a
+/// uniformly random `u` in `(0, 1)`, and the `amount` items with the largest
+/// keys are kept in a bounded min-heap. This yields a weighted sample
+/// without replacement in a single pass using `O(amount)` memory.
+///
+/// Zero-weight items are never selected. If fewer than `amount`
+/// positive-weight items are present in `weights`, the result simply
+/// contains all of them.
+///
+/// Returns a vector of indices into `weights`, in random order.
+pub fn choose_multiple_weighted_stream<R, I>(rng: &mut R, amount: usize, weights: I) -> Vec<usize>
+where
+    R: Rng + ?Sized,
+    I: IntoIterator<Item = f64>,
+{
+    if amount == 0 {
+        return vec![];
+    }
+    let mut reservoir: BinaryHeap<Reverse<StreamCandidate>> = BinaryHeap::with_capacity(amount);
+    for (index, weight) in weights.into_iter().enumerate() {
+        debug_assert!(weight >= 0.0);
+        if weight <= 0.0 {
+            continue;
+        }
+        let u: f64 = rng.gen_range(f64::MIN_POSITIVE..1.0);
+        let key = u.powf(1.0 / weight);
+        if reservoir.len() < amount {
+            reservoir.push(Reverse(StreamCandidate { key, index }));
+        } else if key > reservoir.peek().expect("reservoir is full").0.key {
+            reservoir.pop();
+            reservoir.push(Reverse(StreamCandidate { key, index }));
+        }
+    }
+    let mut samples: Vec<usize> = reservoir.into_iter().map(|Reverse(c)| c.index).collect();
+    samples.shuffle(rng);
+    samples
+}
+
+/// A reusable, precomputed weighted sampler
+///
+/// Building a [WeightedSampler] from a `weights` array computes an alias
+/// table once, using Vose's method, so that repeated single draws from the
+/// same weights cost `O(1)` instead of rebuilding a cumulative-sum vector on
+/// every call. This is useful when the same weights are sampled from many
+/// times, for example a fixed genetic-algorithm population. All weights must
+/// be greater than or equal to zero. If all of the weights are equal, even if
+/// they are all zero, then each element has an equal likelihood of being
+/// selected.
+pub struct WeightedSampler {
+    prob: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl WeightedSampler {
+    /// Precompute the alias table for the given `weights`.
+    pub fn new(weights: &[f64]) -> Self {
+        assert!(!weights.is_empty(), "{NO_DATA}");
+        let n = weights.len();
+        for &weight in weights {
+            debug_assert!(weight >= 0.0);
+        }
+        let total: f64 = weights.iter().sum();
+        assert!(total.is_finite());
+        // Check for all zero weights.
+        if total <= f64::EPSILON * n as f64 {
+            return Self {
+                prob: vec![1.0; n],
+                alias: (0..n).collect(),
+            };
+        }
+        // Scale the weights so that their average is 1.
+        let mut scaled: Vec<f64> = weights.iter().map(|&w| w * n as f64 / total).collect();
+        let mut small: Vec<usize> = (0..n).filter(|&i| scaled[i] < 1.0).collect();
+        let mut large: Vec<usize> = (0..n).filter(|&i| scaled[i] >= 1.0).collect();
+        let mut prob = vec![0.0; n];
+        let mut alias = vec![0; n];
+        while !small.is_empty() && !large.is_empty() {
+            let s = small.pop().expect("checked: small is not empty");
+            let l = large.pop().expect("checked: large is not empty");
+            prob[s] = scaled[s];
+            alias[s] = l;
+            scaled[l] = (scaled[l] + scaled[s]) - 1.0;
+            if scaled[l] < 1.0 {
+                small.push(l);
+            } else {
+                large.push(l);
+            }
+        }
+        // Flush any stragglers left behind by floating-point rounding.
+        while let Some(l) = large.pop() {
+            prob[l] = 1.0;
+        }
+        while let Some(s) = small.pop() {
+            prob[s] = 1.0;
+        }
+        Self { prob, alias }
+    }
+
+    /// Draw a single index, with likelihood proportional to its weight.
+    ///
+    /// Returns an index into the weights array that this sampler was built from.
+    pub fn sample<R>(&self, rng: &mut R) -> usize
+    where
+        R: Rng + ?Sized,
+    {
+        let column = rng.gen_range(0..self.prob.len());
+        if rng.gen::<f64>() < self.prob[column] {
+            column
+        } else {
+            self.alias[column]
+        }
+    }
+
+    /// Draw `amount` indices, with repetition, with likelihood proportional to their weight.
+    ///
+    /// Returns a vector of indices into the weights array that this sampler was built from.
+    pub fn sample_multiple<R>(&self, rng: &mut R, amount: usize) -> Vec<usize>
+    where
+        R: Rng + ?Sized,
+    {
+        (0..amount).map(|_| self.sample(rng)).collect()
+    }
+}
+
+/// The stochastic universal sampling algorithm, writing into a caller-provided buffer
+///
+/// Behaves exactly like [choose_multiple_weighted], except the resulting
+/// indices are written into `out` instead of allocating a fresh vector.
+/// `out` is cleared and its existing backing storage is reused, which keeps
+/// the hot loop allocation-free across repeated calls, for example in an
+/// evolutionary-algorithm inner loop. When all of the weights are zero, the
+/// indices come from [choose_multiple], which allocates a temporary vector.
+pub fn choose_multiple_weighted_buf<R>(
+    rng: &mut R,
+    amount: usize,
+    weights: &[f64],
+    out: &mut Vec<usize>,
+) where
+    R: Rng + ?Sized,
+{
+    out.clear();
+    if amount == 0 {
+        return;
+    } else {
+        assert!(!weights.is_empty(), "{NO_DATA}");
+    }
+    for &weight in weights {
+        debug_assert!(weight >= 0.0);
+    }
+    // Check for all zero weights.
+    let total_weight: f64 = weights.iter().sum();
+    if total_weight <= f64::EPSILON * weights.len() as f64 {
+        out.extend(choose_multiple(rng, amount, weights.len()));
+        return;
+    }
+    assert!(total_weight.is_finite());
+    // Generate the random numbers to sample from the weights cumsum.
+    let arm_spacing = total_weight / (amount as f64);
+    let arm_offset = rng.gen::<f64>() * arm_spacing;
+    // Find the indices of random numbers in the weights cumsum, walking the
+    // cumulative sum incrementally alongside `idx` instead of materializing
+    // a separate cumulative-sum vector.
+    out.reserve(amount);
+    let mut idx = 0;
+    let mut cumulative = weights[0];
+    for arm in 0..amount {
+        let arm = (arm as f64) * arm_spacing + arm_offset;
+        while idx < weights.len() - 1 && cumulative < arm {
+            idx += 1;
+            cumulative += weights[idx];
+        }
+        out.push(idx);
+    }
+    // Shuffle the random sample to break up any runs of repeated elements.
+    out.shuffle(rng);
+}
+
+/// The stochastic universal sampling algorithm, over a slice of weighted elements
+///
+/// Chooses `amount` elements from `items`, with repetition, and in random
+/// order, with likelihood proportional to each element's paired weight.
+/// This mirrors `SliceRandom` by handing back references to the chosen
+/// elements directly, instead of indices that the caller must look up in a
+/// separate, parallel weights array. Forwards to [choose_multiple_weighted].
+///
+/// Returns a vector of references into `items`.
+pub fn choose_multiple_weighted_from<'a, R, T>(
+    rng: &mut R,
+    amount: usize,
+    items: &'a [(T, f64)],
+) -> Vec<&'a T>
+where
+    R: Rng + ?Sized,
+{
+    let weights: Vec<f64> = items.iter().map(|(_, weight)| *weight).collect();
+    choose_multiple_weighted(rng, amount, &weights)
+        .into_iter()
+        .map(|idx| &items[idx].0)
+        .collect()
+}
+
+/// Choose a single element from `items`, with likelihood proportional to its paired weight
+///
+/// Returns a reference into `items`.
+pub fn choose_one_weighted<'a, R, T>(rng: &mut R, items: &'a [(T, f64)]) -> &'a T
+where
+    R: Rng + ?Sized,
+{
+    choose_multiple_weighted_from(rng, 1, items)[0]
+}
+
 #[cfg(test)]
 mod tests {
     use super::choose_multiple_weighted as sus;
+    use super::choose_multiple_weighted_without_replacement as sus_no_repeat;
 
     fn assert_data_eq(a: &mut [usize], b: &mut [usize]) {
         a.sort();
@@ -209,4 +712,272 @@ mod tests {
         let elapsed_time = start_time.elapsed();
         println!("Elapsed time: {elapsed_time:?}");
     }
+
+    #[test]
+    fn no_repeat_no_data() {
+        let mut rng = rand::thread_rng();
+        assert_data_eq(&mut sus_no_repeat(&mut rng, 0, &[]), &mut []);
+        assert_data_eq(&mut sus_no_repeat(&mut rng, 0, &[1.0, 2.0, 3.0]), &mut []);
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_repeat_no_data_panic() {
+        let mut rng = rand::thread_rng();
+        sus_no_repeat(&mut rng, 100, &[]);
+    }
+
+    #[test]
+    fn no_repeat_not_enough_data() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(sus_no_repeat(&mut rng, 2, &[1.0]), vec![0]);
+    }
+
+    #[test]
+    fn no_repeat_zero_data() {
+        let mut rng = rand::thread_rng();
+        assert_data_eq(
+            &mut sus_no_repeat(&mut rng, 10, &[0.0; 10]),
+            &mut [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        );
+        assert_data_eq(&mut sus_no_repeat(&mut rng, 6, &[0.0; 3]), &mut [0, 1, 2]);
+    }
+
+    #[test]
+    fn no_repeat_it_works() {
+        let mut rng = rand::thread_rng();
+        assert_data_eq(
+            &mut sus_no_repeat(&mut rng, 2, &[1.0, 0.0, 1.0]),
+            &mut [0, 2],
+        );
+        assert_data_eq(
+            &mut sus_no_repeat(&mut rng, 3, &[2.0, 0.0, 1.0]),
+            &mut [0, 1, 2],
+        );
+    }
+
+    #[test]
+    fn no_repeat_tiny_positive_weight_not_stranded() {
+        let mut rng = rand::thread_rng();
+        assert_data_eq(
+            &mut sus_no_repeat(&mut rng, 3, &[1.0, 1.0, 1e-300]),
+            &mut [0, 1, 2],
+        );
+    }
+
+    #[test]
+    fn no_repeat_never_duplicates() {
+        let mut rng = rand::thread_rng();
+        let weights: Vec<f64> = (0..200).map(|i| (i + 1) as f64).collect();
+        let mut samples = sus_no_repeat(&mut rng, 200, &weights);
+        samples.sort();
+        samples.dedup();
+        assert_eq!(samples.len(), 200);
+    }
+
+    #[test]
+    fn no_repeat_random_order() {
+        let mut rng = rand::thread_rng();
+        let mut a = sus_no_repeat(&mut rng, 2000, &[1.0; 2000]);
+        let mut b = sus_no_repeat(&mut rng, 2000, &[1.0; 2000]);
+        assert!(a != b);
+        assert_data_eq(&mut a, &mut b);
+    }
+
+    #[test]
+    #[should_panic]
+    fn sampler_no_data() {
+        super::WeightedSampler::new(&[]);
+    }
+
+    #[test]
+    fn sampler_zero_data() {
+        let mut rng = rand::thread_rng();
+        let sampler = super::WeightedSampler::new(&[0.0; 10]);
+        let samples = sampler.sample_multiple(&mut rng, 1000);
+        assert!(samples.iter().all(|&idx| idx < 10));
+        assert!(
+            samples
+                .iter()
+                .copied()
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+                > 1
+        );
+    }
+
+    #[test]
+    fn sampler_round_robin() {
+        let mut rng = rand::thread_rng();
+        let sampler = super::WeightedSampler::new(&[1.0; 3]);
+        for idx in sampler.sample_multiple(&mut rng, 100) {
+            assert!(idx < 3);
+        }
+    }
+
+    #[test]
+    fn sampler_matches_weights() {
+        let mut rng = rand::thread_rng();
+        let sampler = super::WeightedSampler::new(&[1.0, 0.0, 3.0]);
+        let samples = sampler.sample_multiple(&mut rng, 10_000);
+        assert!(samples.iter().all(|&idx| idx != 1));
+        let zero_count = samples.iter().filter(|&&idx| idx == 0).count();
+        let two_count = samples.iter().filter(|&&idx| idx == 2).count();
+        // With weights 1:3, index 2 should be drawn roughly three times as
+        // often as index 0; allow plenty of slack to avoid a flaky test.
+        assert!(two_count > zero_count * 2);
+    }
+
+    #[test]
+    fn sampler_sample_one() {
+        let mut rng = rand::thread_rng();
+        let mut data = [0.0; 10000];
+        data[1234] = 0.0000001;
+        let sampler = super::WeightedSampler::new(&data);
+        assert_eq!(sampler.sample(&mut rng), 1234);
+    }
+
+    #[test]
+    fn checked_no_data() {
+        let mut rng = rand::thread_rng();
+        assert_data_eq(
+            &mut super::choose_multiple_weighted_checked(&mut rng, 0, &[] as &[u32]).unwrap(),
+            &mut [],
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn checked_no_data_panic() {
+        let mut rng = rand::thread_rng();
+        super::choose_multiple_weighted_checked(&mut rng, 100, &[] as &[u32]).unwrap();
+    }
+
+    #[test]
+    fn checked_integer_weights() {
+        let mut rng = rand::thread_rng();
+        let mut samples =
+            super::choose_multiple_weighted_checked(&mut rng, 6, &[1u32, 2, 3]).unwrap();
+        assert_data_eq(&mut samples, &mut [0, 1, 1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn checked_invalid_weight() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(
+            super::choose_multiple_weighted_checked(&mut rng, 1, &[1.0, -1.0]),
+            Err(super::WeightError::InvalidWeight)
+        );
+        assert_eq!(
+            super::choose_multiple_weighted_checked(&mut rng, 1, &[1.0, f64::INFINITY]),
+            Err(super::WeightError::InvalidWeight)
+        );
+    }
+
+    #[test]
+    fn checked_overflow() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(
+            super::choose_multiple_weighted_checked(&mut rng, 1, &[u32::MAX, u32::MAX]),
+            Err(super::WeightError::Overflow)
+        );
+    }
+
+    #[test]
+    fn checked_zero_weights() {
+        let mut rng = rand::thread_rng();
+        assert_data_eq(
+            &mut super::choose_multiple_weighted_checked(&mut rng, 10, &[0u64; 10]).unwrap(),
+            &mut [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        );
+    }
+
+    #[test]
+    fn stream_no_data() {
+        let mut rng = rand::thread_rng();
+        assert_data_eq(
+            &mut super::choose_multiple_weighted_stream(&mut rng, 0, Vec::new()),
+            &mut [],
+        );
+        assert_data_eq(
+            &mut super::choose_multiple_weighted_stream(&mut rng, 5, Vec::new()),
+            &mut [],
+        );
+    }
+
+    #[test]
+    fn stream_not_enough_data() {
+        let mut rng = rand::thread_rng();
+        assert_data_eq(
+            &mut super::choose_multiple_weighted_stream(&mut rng, 5, vec![1.0, 2.0]),
+            &mut [0, 1],
+        );
+    }
+
+    #[test]
+    fn stream_zero_weights_excluded() {
+        let mut rng = rand::thread_rng();
+        assert_data_eq(
+            &mut super::choose_multiple_weighted_stream(&mut rng, 2, vec![1.0, 0.0, 1.0, 0.0]),
+            &mut [0, 2],
+        );
+    }
+
+    #[test]
+    fn stream_never_duplicates() {
+        let mut rng = rand::thread_rng();
+        let weights: Vec<f64> = (0..200).map(|i| (i + 1) as f64).collect();
+        let mut samples = super::choose_multiple_weighted_stream(&mut rng, 50, weights);
+        assert_eq!(samples.len(), 50);
+        samples.sort();
+        samples.dedup();
+        assert_eq!(samples.len(), 50);
+    }
+
+    #[test]
+    fn stream_random_order() {
+        let mut rng = rand::thread_rng();
+        let weights = vec![1.0; 2000];
+        let mut a = super::choose_multiple_weighted_stream(&mut rng, 2000, weights.clone());
+        let mut b = super::choose_multiple_weighted_stream(&mut rng, 2000, weights);
+        assert!(a != b);
+        assert_data_eq(&mut a, &mut b);
+    }
+
+    #[test]
+    fn buf_reuses_capacity() {
+        let mut rng = rand::thread_rng();
+        let mut out = Vec::with_capacity(10);
+        super::choose_multiple_weighted_buf(&mut rng, 6, &[1.0, 2.0, 3.0], &mut out);
+        assert_data_eq(&mut out.clone(), &mut [0, 1, 1, 2, 2, 2]);
+        assert_eq!(out.capacity(), 10);
+        super::choose_multiple_weighted_buf(&mut rng, 3, &[1.0, 0.0, 0.0], &mut out);
+        assert_eq!(out, vec![0, 0, 0]);
+        assert_eq!(out.capacity(), 10);
+    }
+
+    #[test]
+    fn choose_multiple_weighted_from_forwards() {
+        let mut rng = rand::thread_rng();
+        let items = [("a", 1.0), ("b", 0.0), ("c", 1.0)];
+        let chosen = super::choose_multiple_weighted_from(&mut rng, 2, &items);
+        let mut chosen: Vec<&str> = chosen.into_iter().copied().collect();
+        chosen.sort();
+        assert_eq!(chosen, vec!["a", "c"]);
+    }
+
+    #[test]
+    fn choose_one_weighted_picks_the_only_option() {
+        let mut rng = rand::thread_rng();
+        let items = [("only", 1.0)];
+        assert_eq!(*super::choose_one_weighted(&mut rng, &items), "only");
+    }
+
+    #[test]
+    #[should_panic]
+    fn choose_one_weighted_no_data() {
+        let mut rng = rand::thread_rng();
+        let items: [(&str, f64); 0] = [];
+        super::choose_one_weighted(&mut rng, &items);
+    }
 }